@@ -1,6 +1,16 @@
 //! I2S (inter-IC Sound) communication using SPI peripherals
 
+use core::ops::Deref;
+
+use crate::dma::{
+    config::DmaConfig,
+    traits::{DMASet, PeriAddress},
+    MemoryToPeripheral, PeripheralToMemory, Transfer,
+};
+use crate::pac;
+use crate::rcc::Clocks;
 use crate::spi;
+use crate::time::Hertz;
 
 // I2S pins are mostly the same as the corresponding SPI pins:
 // MOSI -> SD
@@ -15,7 +25,16 @@ pub trait PinWs<SPI> {}
 /// A pin that can be used as CK (bit clock)
 pub trait PinCk<SPI> {}
 /// A pin that can be used as MCK (master clock output)
-pub trait PinMck<SPI> {}
+pub trait PinMck<SPI> {
+    /// True for a real master clock pin, false for the `NoMasterClock` placeholder or a `None`
+    /// master clock pin selection
+    fn is_master_clock(&self) -> bool {
+        true
+    }
+}
+/// A pin that can be used as SD (serial data) for the I2Sxext extension block that provides the
+/// other half of a full-duplex I2S interface built on `SPI`
+pub trait PinSdExt<SPI> {}
 
 /// Each MOSI pin can also be used as SD
 impl<P, SPI> PinSd<SPI> for P where P: spi::PinMosi<SPI> {}
@@ -30,7 +49,11 @@ mod sealed {
 }
 
 /// A set of pins configured for I2S communication: (WS, CK, MCLK, SD)
-pub trait Pins<SPI> {}
+pub trait Pins<SPI> {
+    /// True if the MCK pin in this set is a real pin rather than `NoMasterClock` or a `None`
+    /// master clock pin selection
+    fn master_clock(&self) -> bool;
+}
 
 impl<SPI, PWS, PCK, PMCLK, PSD> Pins<SPI> for (PWS, PCK, PMCLK, PSD)
 where
@@ -39,6 +62,9 @@ where
     PMCLK: PinMck<SPI>,
     PSD: PinSd<SPI>,
 {
+    fn master_clock(&self) -> bool {
+        self.2.is_master_clock()
+    }
 }
 
 /// Master clock (MCK) pins
@@ -273,167 +299,2197 @@ mod ws_pins {
     }
 }
 
-/// An SPI peripheral that can be used in I2S mode
-pub trait Enable: sealed::Sealed {
-    /// Enables the peripheral by setting the corresponding enable bit in an RCC register
-    fn enable();
-}
+/// Enum-based pin selection for WS and MCK, complementing the trait-based `Pins` tuple above
+/// with one concrete type per SPI instance that can be chosen at runtime (e.g. from a board
+/// configuration) and built `From` any of that instance's valid pins.
+///
+/// `CK` and `SD` keep using the generic `PinCk`/`PinSd` traits directly: their valid pins are
+/// defined by the underlying `spi` module rather than enumerated here, so there is no closed set
+/// to wrap in an enum. `WS` is always required, so its enums have no "unselected" variant; `MCK`
+/// is optional, so each `MckPinN` has a `None` variant that replaces `NoMasterClock` - pass
+/// `MckPinN::None` where the old code passed `NoMasterClock`.
+mod pin_enums {
+    use crate::gpio::{
+        gpioa::{PA11, PA15, PA3, PA4, PA6},
+        gpiob::{PB1, PB10, PB12, PB4, PB9},
+        gpioc::{PC4, PC6, PC7},
+        gpiod::PD1,
+        gpioe::{PE11, PE4},
+        gpioi::PI0,
+        Alternate, AF5, AF6, AF7,
+    };
+    use crate::i2s::sealed::Sealed;
+    use crate::i2s::{PinMck, PinWs};
+    use crate::pac::{SPI1, SPI2, SPI3, SPI4, SPI5};
 
-// All STM32F4 models use the same bits in APB1ENR, APB2ENR, APB1RSTR, and APB2RSTR to enable
-// and reset the SPI peripherals.
-// SPI1: APB2 bit 12
-// SPI2: APB1 bit 14
-// SPI3: APB1 bit 15
-// SPI4: APB2 bit 13
-// SPI5: APB2 bit 20
+    /// WS pin selection for SPI1/I2S1 (only present on some models)
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+        feature = "stm32f446",
+    ))]
+    pub enum WsPin1 {
+        /// PA4, alternate function 5
+        Pa4(PA4<Alternate<AF5>>),
+        /// PA15, alternate function 5
+        Pa15(PA15<Alternate<AF5>>),
+    }
 
-#[cfg(any(
-    feature = "stm32f410",
-    feature = "stm32f411",
-    feature = "stm32f412",
-    feature = "stm32f413",
-    feature = "stm32f423",
-    feature = "stm32f446",
-))]
-mod spi1 {
-    use super::sealed::Sealed;
-    use super::{Enable, NoMasterClock, PinMck};
-    use crate::bb;
-    use crate::pac::{RCC, SPI1};
-    impl Sealed for SPI1 {}
-    impl Enable for SPI1 {
-        fn enable() {
-            unsafe {
-                // NOTE(unsafe) this reference will only be used for atomic writes with no side effects.
-                let rcc = &(*RCC::ptr());
-                const SPI_BIT: u8 = 12;
-                // Enable clock, enable reset, clear, reset
-                bb::set(&rcc.apb2enr, SPI_BIT);
-                bb::set(&rcc.apb2rstr, SPI_BIT);
-                bb::clear(&rcc.apb2rstr, SPI_BIT);
-            }
+    // The wrapped pin types aren't guaranteed to implement `Debug`, so this only reports which
+    // pin was selected rather than deriving over the field.
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+        feature = "stm32f446",
+    ))]
+    impl core::fmt::Debug for WsPin1 {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str(match self {
+                WsPin1::Pa4(_) => "WsPin1::Pa4",
+                WsPin1::Pa15(_) => "WsPin1::Pa15",
+            })
         }
     }
-    impl PinMck<SPI1> for NoMasterClock {}
-}
 
-// All STM32F4 models support SPI2/I2S2
-mod spi2 {
-    use super::sealed::Sealed;
-    use super::{Enable, NoMasterClock, PinMck};
-    use crate::bb;
-    use crate::pac::{RCC, SPI2};
-    impl Sealed for SPI2 {}
-    impl Enable for SPI2 {
-        fn enable() {
-            unsafe {
-                // NOTE(unsafe) this reference will only be used for atomic writes with no side effects.
-                let rcc = &(*RCC::ptr());
-                const SPI_BIT: u8 = 14;
-                // Enable clock, enable reset, clear, reset
-                bb::set(&rcc.apb1enr, SPI_BIT);
-                bb::set(&rcc.apb1rstr, SPI_BIT);
-                bb::clear(&rcc.apb1rstr, SPI_BIT);
-            }
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+        feature = "stm32f446",
+    ))]
+    impl Sealed for WsPin1 {}
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+        feature = "stm32f446",
+    ))]
+    impl PinWs<SPI1> for WsPin1 {}
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+        feature = "stm32f446",
+    ))]
+    impl From<PA4<Alternate<AF5>>> for WsPin1 {
+        fn from(pin: PA4<Alternate<AF5>>) -> Self {
+            WsPin1::Pa4(pin)
         }
     }
-    impl PinMck<SPI2> for NoMasterClock {}
-}
-
-// All STM32F4 models except STM32F410 support SPI3/I2S3
-#[cfg(any(
-    feature = "stm32f401",
-    feature = "stm32f405",
-    feature = "stm32f407",
-    feature = "stm32f411",
-    feature = "stm32f412",
-    feature = "stm32f413",
-    feature = "stm32f415",
-    feature = "stm32f417",
-    feature = "stm32f423",
-    feature = "stm32f427",
-    feature = "stm32f429",
-    feature = "stm32f437",
-    feature = "stm32f439",
-    feature = "stm32f446",
-    feature = "stm32f469",
-    feature = "stm32f479",
-))]
-mod spi3 {
-    use super::sealed::Sealed;
-    use super::{Enable, NoMasterClock, PinMck};
-    use crate::bb;
-    use crate::pac::{RCC, SPI3};
-    impl Sealed for SPI3 {}
-    impl Enable for SPI3 {
-        fn enable() {
-            unsafe {
-                // NOTE(unsafe) this reference will only be used for atomic writes with no side effects.
-                let rcc = &(*RCC::ptr());
-                const SPI_BIT: u8 = 15;
-                // Enable clock, enable reset, clear, reset
-                bb::set(&rcc.apb1enr, SPI_BIT);
-                bb::set(&rcc.apb1rstr, SPI_BIT);
-                bb::clear(&rcc.apb1rstr, SPI_BIT);
-            }
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+        feature = "stm32f446",
+    ))]
+    impl From<PA15<Alternate<AF5>>> for WsPin1 {
+        fn from(pin: PA15<Alternate<AF5>>) -> Self {
+            WsPin1::Pa15(pin)
         }
     }
-    impl PinMck<SPI3> for NoMasterClock {}
-}
 
-#[cfg(any(
-    feature = "stm32f411",
-    feature = "stm32f412",
-    feature = "stm32f413",
-    feature = "stm32f423",
-))]
-mod spi4 {
-    use super::sealed::Sealed;
-    use super::{Enable, NoMasterClock, PinMck};
-    use crate::bb;
-    use crate::pac::{RCC, SPI4};
-    impl Sealed for SPI4 {}
-    impl Enable for SPI4 {
-        fn enable() {
-            unsafe {
-                // NOTE(unsafe) this reference will only be used for atomic writes with no side effects.
-                let rcc = &(*RCC::ptr());
-                const SPI_BIT: u8 = 13;
-                // Enable clock, enable reset, clear, reset
-                bb::set(&rcc.apb2enr, SPI_BIT);
-                bb::set(&rcc.apb2rstr, SPI_BIT);
-                bb::clear(&rcc.apb2rstr, SPI_BIT);
-            }
+    /// WS pin selection for SPI2/I2S2
+    pub enum WsPin2 {
+        /// PB9, alternate function 5
+        Pb9(PB9<Alternate<AF5>>),
+        /// PB12, alternate function 5
+        Pb12(PB12<Alternate<AF5>>),
+        /// PA11, alternate function 5 (only on the STM32F413/F423)
+        #[cfg(any(feature = "stm32f413", feature = "stm32f423"))]
+        Pa11(PA11<Alternate<AF5>>),
+        /// PB4, alternate function 7 (only on the STM32F446)
+        #[cfg(feature = "stm32f446")]
+        Pb4(PB4<Alternate<AF7>>),
+        /// PD1, alternate function 7 (only on the STM32F446)
+        #[cfg(feature = "stm32f446")]
+        Pd1(PD1<Alternate<AF7>>),
+        /// PI0, alternate function 5 (not available on the STM32F401/F410/F411/F412/F413/F423/F446)
+        #[cfg(any(
+            feature = "stm32f405",
+            feature = "stm32f407",
+            feature = "stm32f415",
+            feature = "stm32f417",
+            feature = "stm32f427",
+            feature = "stm32f429",
+            feature = "stm32f437",
+            feature = "stm32f439",
+            feature = "stm32f469",
+            feature = "stm32f479",
+        ))]
+        Pi0(PI0<Alternate<AF5>>),
+    }
+
+    // The wrapped pin types aren't guaranteed to implement `Debug`, so this only reports which
+    // pin was selected rather than deriving over the field.
+    impl core::fmt::Debug for WsPin2 {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str(match self {
+                WsPin2::Pb9(_) => "WsPin2::Pb9",
+                WsPin2::Pb12(_) => "WsPin2::Pb12",
+                #[cfg(any(feature = "stm32f413", feature = "stm32f423"))]
+                WsPin2::Pa11(_) => "WsPin2::Pa11",
+                #[cfg(feature = "stm32f446")]
+                WsPin2::Pb4(_) => "WsPin2::Pb4",
+                #[cfg(feature = "stm32f446")]
+                WsPin2::Pd1(_) => "WsPin2::Pd1",
+                #[cfg(any(
+                    feature = "stm32f405",
+                    feature = "stm32f407",
+                    feature = "stm32f415",
+                    feature = "stm32f417",
+                    feature = "stm32f427",
+                    feature = "stm32f429",
+                    feature = "stm32f437",
+                    feature = "stm32f439",
+                    feature = "stm32f469",
+                    feature = "stm32f479",
+                ))]
+                WsPin2::Pi0(_) => "WsPin2::Pi0",
+            })
         }
     }
-    impl PinMck<SPI4> for NoMasterClock {}
-}
 
-#[cfg(any(
-    feature = "stm32f410",
-    feature = "stm32f411",
-    feature = "stm32f412",
-    feature = "stm32f413",
-    feature = "stm32f423",
-))]
-mod spi5 {
-    use super::sealed::Sealed;
-    use super::{Enable, NoMasterClock, PinMck};
-    use crate::bb;
-    use crate::pac::{RCC, SPI5};
-    impl Sealed for SPI5 {}
-    impl Enable for SPI5 {
-        fn enable() {
-            unsafe {
-                // NOTE(unsafe) this reference will only be used for atomic writes with no side effects.
-                let rcc = &(*RCC::ptr());
-                const SPI_BIT: u8 = 20;
-                // Enable clock, enable reset, clear, reset
-                bb::set(&rcc.apb2enr, SPI_BIT);
-                bb::set(&rcc.apb2rstr, SPI_BIT);
-                bb::clear(&rcc.apb2rstr, SPI_BIT);
-            }
+    impl Sealed for WsPin2 {}
+    impl PinWs<SPI2> for WsPin2 {}
+
+    impl From<PB9<Alternate<AF5>>> for WsPin2 {
+        fn from(pin: PB9<Alternate<AF5>>) -> Self {
+            WsPin2::Pb9(pin)
         }
     }
-    impl PinMck<SPI5> for NoMasterClock {}
-}
+    impl From<PB12<Alternate<AF5>>> for WsPin2 {
+        fn from(pin: PB12<Alternate<AF5>>) -> Self {
+            WsPin2::Pb12(pin)
+        }
+    }
+    #[cfg(any(feature = "stm32f413", feature = "stm32f423"))]
+    impl From<PA11<Alternate<AF5>>> for WsPin2 {
+        fn from(pin: PA11<Alternate<AF5>>) -> Self {
+            WsPin2::Pa11(pin)
+        }
+    }
+    #[cfg(feature = "stm32f446")]
+    impl From<PB4<Alternate<AF7>>> for WsPin2 {
+        fn from(pin: PB4<Alternate<AF7>>) -> Self {
+            WsPin2::Pb4(pin)
+        }
+    }
+    #[cfg(feature = "stm32f446")]
+    impl From<PD1<Alternate<AF7>>> for WsPin2 {
+        fn from(pin: PD1<Alternate<AF7>>) -> Self {
+            WsPin2::Pd1(pin)
+        }
+    }
+    #[cfg(any(
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    impl From<PI0<Alternate<AF5>>> for WsPin2 {
+        fn from(pin: PI0<Alternate<AF5>>) -> Self {
+            WsPin2::Pi0(pin)
+        }
+    }
+
+    /// WS pin selection for SPI3/I2S3 (not available on the STM32F410, which has no SPI3)
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    pub enum WsPin3 {
+        /// PA4, alternate function 6
+        Pa4(PA4<Alternate<AF6>>),
+        /// PA15, alternate function 6
+        Pa15(PA15<Alternate<AF6>>),
+    }
+
+    // The wrapped pin types aren't guaranteed to implement `Debug`, so this only reports which
+    // pin was selected rather than deriving over the field.
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    impl core::fmt::Debug for WsPin3 {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str(match self {
+                WsPin3::Pa4(_) => "WsPin3::Pa4",
+                WsPin3::Pa15(_) => "WsPin3::Pa15",
+            })
+        }
+    }
+
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    impl Sealed for WsPin3 {}
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    impl PinWs<SPI3> for WsPin3 {}
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    impl From<PA4<Alternate<AF6>>> for WsPin3 {
+        fn from(pin: PA4<Alternate<AF6>>) -> Self {
+            WsPin3::Pa4(pin)
+        }
+    }
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    impl From<PA15<Alternate<AF6>>> for WsPin3 {
+        fn from(pin: PA15<Alternate<AF6>>) -> Self {
+            WsPin3::Pa15(pin)
+        }
+    }
+
+    /// WS pin selection for SPI4/I2S4 (only present on the STM32F411/F412/F413/F423)
+    #[cfg(any(
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    pub enum WsPin4 {
+        /// PB12, alternate function 6
+        Pb12(PB12<Alternate<AF6>>),
+        /// PE4, alternate function 5
+        Pe4(PE4<Alternate<AF5>>),
+        /// PE11, alternate function 5
+        Pe11(PE11<Alternate<AF5>>),
+    }
+
+    // The wrapped pin types aren't guaranteed to implement `Debug`, so this only reports which
+    // pin was selected rather than deriving over the field.
+    #[cfg(any(
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl core::fmt::Debug for WsPin4 {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str(match self {
+                WsPin4::Pb12(_) => "WsPin4::Pb12",
+                WsPin4::Pe4(_) => "WsPin4::Pe4",
+                WsPin4::Pe11(_) => "WsPin4::Pe11",
+            })
+        }
+    }
+
+    #[cfg(any(
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl Sealed for WsPin4 {}
+    #[cfg(any(
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl PinWs<SPI4> for WsPin4 {}
+    #[cfg(any(
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl From<PB12<Alternate<AF6>>> for WsPin4 {
+        fn from(pin: PB12<Alternate<AF6>>) -> Self {
+            WsPin4::Pb12(pin)
+        }
+    }
+    #[cfg(any(
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl From<PE4<Alternate<AF5>>> for WsPin4 {
+        fn from(pin: PE4<Alternate<AF5>>) -> Self {
+            WsPin4::Pe4(pin)
+        }
+    }
+    #[cfg(any(
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl From<PE11<Alternate<AF5>>> for WsPin4 {
+        fn from(pin: PE11<Alternate<AF5>>) -> Self {
+            WsPin4::Pe11(pin)
+        }
+    }
+
+    /// WS pin selection for SPI5/I2S5 (only present on the STM32F410/F411/F412/F413/F423)
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    pub enum WsPin5 {
+        /// PB1, alternate function 6
+        Pb1(PB1<Alternate<AF6>>),
+        /// PE4, alternate function 6 (not available on the STM32F410)
+        #[cfg(any(
+            feature = "stm32f411",
+            feature = "stm32f412",
+            feature = "stm32f413",
+            feature = "stm32f423",
+        ))]
+        Pe4(PE4<Alternate<AF6>>),
+        /// PE11, alternate function 6 (not available on the STM32F410)
+        #[cfg(any(
+            feature = "stm32f411",
+            feature = "stm32f412",
+            feature = "stm32f413",
+            feature = "stm32f423",
+        ))]
+        Pe11(PE11<Alternate<AF6>>),
+    }
+
+    // The wrapped pin types aren't guaranteed to implement `Debug`, so this only reports which
+    // pin was selected rather than deriving over the field.
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl core::fmt::Debug for WsPin5 {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str(match self {
+                WsPin5::Pb1(_) => "WsPin5::Pb1",
+                #[cfg(any(
+                    feature = "stm32f411",
+                    feature = "stm32f412",
+                    feature = "stm32f413",
+                    feature = "stm32f423",
+                ))]
+                WsPin5::Pe4(_) => "WsPin5::Pe4",
+                #[cfg(any(
+                    feature = "stm32f411",
+                    feature = "stm32f412",
+                    feature = "stm32f413",
+                    feature = "stm32f423",
+                ))]
+                WsPin5::Pe11(_) => "WsPin5::Pe11",
+            })
+        }
+    }
+
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl Sealed for WsPin5 {}
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl PinWs<SPI5> for WsPin5 {}
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl From<PB1<Alternate<AF6>>> for WsPin5 {
+        fn from(pin: PB1<Alternate<AF6>>) -> Self {
+            WsPin5::Pb1(pin)
+        }
+    }
+    #[cfg(any(
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl From<PE4<Alternate<AF6>>> for WsPin5 {
+        fn from(pin: PE4<Alternate<AF6>>) -> Self {
+            WsPin5::Pe4(pin)
+        }
+    }
+    #[cfg(any(
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl From<PE11<Alternate<AF6>>> for WsPin5 {
+        fn from(pin: PE11<Alternate<AF6>>) -> Self {
+            WsPin5::Pe11(pin)
+        }
+    }
+
+    /// MCK pin selection for SPI1/I2S1
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+        feature = "stm32f446",
+    ))]
+    pub enum MckPin1 {
+        /// PC4, alternate function 5 (not available on the STM32F410)
+        #[cfg(any(
+            feature = "stm32f412",
+            feature = "stm32f413",
+            feature = "stm32f423",
+            feature = "stm32f446",
+        ))]
+        Pc4(PC4<Alternate<AF5>>),
+        /// PC7, alternate function 6 (only on the STM32F410, where it outputs I2S1's clock)
+        #[cfg(feature = "stm32f410")]
+        Pc7(PC7<Alternate<AF6>>),
+        /// PB10, alternate function 6 (only on the STM32F410, where it outputs I2S1's clock)
+        #[cfg(feature = "stm32f410")]
+        Pb10(PB10<Alternate<AF6>>),
+        /// No master clock output
+        None,
+    }
+
+    // The wrapped pin types aren't guaranteed to implement `Debug`, so this only reports which
+    // pin was selected rather than deriving over the field.
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+        feature = "stm32f446",
+    ))]
+    impl core::fmt::Debug for MckPin1 {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str(match self {
+                #[cfg(any(
+                    feature = "stm32f412",
+                    feature = "stm32f413",
+                    feature = "stm32f423",
+                    feature = "stm32f446",
+                ))]
+                MckPin1::Pc4(_) => "MckPin1::Pc4",
+                #[cfg(feature = "stm32f410")]
+                MckPin1::Pc7(_) => "MckPin1::Pc7",
+                #[cfg(feature = "stm32f410")]
+                MckPin1::Pb10(_) => "MckPin1::Pb10",
+                MckPin1::None => "MckPin1::None",
+            })
+        }
+    }
+
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+        feature = "stm32f446",
+    ))]
+    impl Sealed for MckPin1 {}
+    #[cfg(any(
+        feature = "stm32f410",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+        feature = "stm32f446",
+    ))]
+    impl PinMck<SPI1> for MckPin1 {
+        fn is_master_clock(&self) -> bool {
+            !matches!(self, MckPin1::None)
+        }
+    }
+    #[cfg(any(
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+        feature = "stm32f446",
+    ))]
+    impl From<PC4<Alternate<AF5>>> for MckPin1 {
+        fn from(pin: PC4<Alternate<AF5>>) -> Self {
+            MckPin1::Pc4(pin)
+        }
+    }
+    #[cfg(feature = "stm32f410")]
+    impl From<PC7<Alternate<AF6>>> for MckPin1 {
+        fn from(pin: PC7<Alternate<AF6>>) -> Self {
+            MckPin1::Pc7(pin)
+        }
+    }
+    #[cfg(feature = "stm32f410")]
+    impl From<PB10<Alternate<AF6>>> for MckPin1 {
+        fn from(pin: PB10<Alternate<AF6>>) -> Self {
+            MckPin1::Pb10(pin)
+        }
+    }
+
+    /// MCK pin selection for SPI2/I2S2
+    pub enum MckPin2 {
+        /// PC6, alternate function 5 (supported on every STM32F4 model)
+        Pc6(PC6<Alternate<AF5>>),
+        /// PA3, alternate function 5 (only on the STM32F411/F412/F413/F423)
+        #[cfg(any(
+            feature = "stm32f411",
+            feature = "stm32f412",
+            feature = "stm32f413",
+            feature = "stm32f423",
+        ))]
+        Pa3(PA3<Alternate<AF5>>),
+        /// PA6, alternate function 6 (only on the STM32F411/F412/F413/F423)
+        #[cfg(any(
+            feature = "stm32f411",
+            feature = "stm32f412",
+            feature = "stm32f413",
+            feature = "stm32f423",
+        ))]
+        Pa6(PA6<Alternate<AF6>>),
+        /// No master clock output
+        None,
+    }
+
+    // The wrapped pin types aren't guaranteed to implement `Debug`, so this only reports which
+    // pin was selected rather than deriving over the field.
+    impl core::fmt::Debug for MckPin2 {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str(match self {
+                MckPin2::Pc6(_) => "MckPin2::Pc6",
+                #[cfg(any(
+                    feature = "stm32f411",
+                    feature = "stm32f412",
+                    feature = "stm32f413",
+                    feature = "stm32f423",
+                ))]
+                MckPin2::Pa3(_) => "MckPin2::Pa3",
+                #[cfg(any(
+                    feature = "stm32f411",
+                    feature = "stm32f412",
+                    feature = "stm32f413",
+                    feature = "stm32f423",
+                ))]
+                MckPin2::Pa6(_) => "MckPin2::Pa6",
+                MckPin2::None => "MckPin2::None",
+            })
+        }
+    }
+
+    impl Sealed for MckPin2 {}
+    impl PinMck<SPI2> for MckPin2 {
+        fn is_master_clock(&self) -> bool {
+            !matches!(self, MckPin2::None)
+        }
+    }
+    impl From<PC6<Alternate<AF5>>> for MckPin2 {
+        fn from(pin: PC6<Alternate<AF5>>) -> Self {
+            MckPin2::Pc6(pin)
+        }
+    }
+    #[cfg(any(
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl From<PA3<Alternate<AF5>>> for MckPin2 {
+        fn from(pin: PA3<Alternate<AF5>>) -> Self {
+            MckPin2::Pa3(pin)
+        }
+    }
+    #[cfg(any(
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl From<PA6<Alternate<AF6>>> for MckPin2 {
+        fn from(pin: PA6<Alternate<AF6>>) -> Self {
+            MckPin2::Pa6(pin)
+        }
+    }
+
+    /// MCK pin selection for SPI3/I2S3 (not available on the STM32F410, which has no SPI3)
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    pub enum MckPin3 {
+        /// PB10, alternate function 6 (only on the STM32F411/F412/F413/F423)
+        #[cfg(any(
+            feature = "stm32f411",
+            feature = "stm32f412",
+            feature = "stm32f413",
+            feature = "stm32f423",
+        ))]
+        Pb10(PB10<Alternate<AF6>>),
+        /// PC7, alternate function 6
+        Pc7(PC7<Alternate<AF6>>),
+        /// No master clock output
+        None,
+    }
+
+    // The wrapped pin types aren't guaranteed to implement `Debug`, so this only reports which
+    // pin was selected rather than deriving over the field.
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    impl core::fmt::Debug for MckPin3 {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str(match self {
+                #[cfg(any(
+                    feature = "stm32f411",
+                    feature = "stm32f412",
+                    feature = "stm32f413",
+                    feature = "stm32f423",
+                ))]
+                MckPin3::Pb10(_) => "MckPin3::Pb10",
+                MckPin3::Pc7(_) => "MckPin3::Pc7",
+                MckPin3::None => "MckPin3::None",
+            })
+        }
+    }
+
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    impl Sealed for MckPin3 {}
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    impl PinMck<SPI3> for MckPin3 {
+        fn is_master_clock(&self) -> bool {
+            !matches!(self, MckPin3::None)
+        }
+    }
+    #[cfg(any(
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f423",
+    ))]
+    impl From<PB10<Alternate<AF6>>> for MckPin3 {
+        fn from(pin: PB10<Alternate<AF6>>) -> Self {
+            MckPin3::Pb10(pin)
+        }
+    }
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    impl From<PC7<Alternate<AF6>>> for MckPin3 {
+        fn from(pin: PC7<Alternate<AF6>>) -> Self {
+            MckPin3::Pc7(pin)
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "stm32f410",
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f423",
+    feature = "stm32f446",
+))]
+pub use pin_enums::WsPin1;
+pub use pin_enums::WsPin2;
+#[cfg(any(
+    feature = "stm32f401",
+    feature = "stm32f405",
+    feature = "stm32f407",
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f415",
+    feature = "stm32f417",
+    feature = "stm32f423",
+    feature = "stm32f427",
+    feature = "stm32f429",
+    feature = "stm32f437",
+    feature = "stm32f439",
+    feature = "stm32f446",
+    feature = "stm32f469",
+    feature = "stm32f479",
+))]
+pub use pin_enums::WsPin3;
+#[cfg(any(
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f423",
+))]
+pub use pin_enums::WsPin4;
+#[cfg(any(
+    feature = "stm32f410",
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f423",
+))]
+pub use pin_enums::WsPin5;
+#[cfg(any(
+    feature = "stm32f410",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f423",
+    feature = "stm32f446",
+))]
+pub use pin_enums::MckPin1;
+pub use pin_enums::MckPin2;
+#[cfg(any(
+    feature = "stm32f401",
+    feature = "stm32f405",
+    feature = "stm32f407",
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f415",
+    feature = "stm32f417",
+    feature = "stm32f423",
+    feature = "stm32f427",
+    feature = "stm32f429",
+    feature = "stm32f437",
+    feature = "stm32f439",
+    feature = "stm32f446",
+    feature = "stm32f469",
+    feature = "stm32f479",
+))]
+pub use pin_enums::MckPin3;
+
+/// SD pins for the I2S2ext and I2S3ext full-duplex extension blocks
+mod ext_sd_pins {
+    macro_rules! pin_sd_ext {
+        ($($PER:ident => $pin:ident<$af:ident>,)+) => {
+            $(
+                impl crate::i2s::sealed::Sealed for $pin<crate::gpio::Alternate<$af>> {}
+                impl crate::i2s::PinSdExt<$PER> for $pin<crate::gpio::Alternate<$af>> {}
+            )+
+        };
+    }
+
+    // All STM32F4 models except STM32F410 support I2S2ext; STM32F410 has SPI2/I2S2 but no
+    // full-duplex extension block for it.
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    mod i2s2ext {
+        use crate::gpio::{
+            gpiob::PB14,
+            gpioc::PC2,
+            AF6,
+        };
+        use crate::pac::SPI2;
+        pin_sd_ext! {
+            SPI2 => PB14<AF6>,
+            SPI2 => PC2<AF6>,
+        }
+    }
+
+    // All STM32F4 models except STM32F410 support SPI3/I2S3, and therefore I2S3ext
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    mod i2s3ext {
+        use crate::gpio::{gpiob::PB4, gpioc::PC11, AF6, AF7};
+        use crate::pac::SPI3;
+        pin_sd_ext! {
+            SPI3 => PB4<AF7>,
+            SPI3 => PC11<AF6>,
+        }
+    }
+}
+
+/// An SPI peripheral that can be used in I2S mode
+pub trait Enable: sealed::Sealed {
+    /// Enables the peripheral by setting the corresponding enable bit in an RCC register
+    fn enable();
+}
+
+/// An SPI peripheral whose registers can be addressed and reconfigured for I2S communication
+pub trait Instance: Enable + Deref<Target = pac::spi1::RegisterBlock> {
+    #[doc(hidden)]
+    fn ptr() -> *const pac::spi1::RegisterBlock;
+}
+
+/// An `Instance` with a companion I2Sxext extension block, letting one I2S interface transmit
+/// and receive simultaneously on the shared WS/CK pins (see `DualI2s`)
+pub trait HasExt: Instance {
+    #[doc(hidden)]
+    fn ext_ptr() -> *const pac::spi1::RegisterBlock;
+    /// Prepares the I2Sxext block for use. On STM32F4 devices, I2Sxext shares its parent SPI/I2S
+    /// peripheral's RCC clock gate and reset rather than having its own, and `I2s::new` has
+    /// already enabled that gate, so this leaves RCC untouched: `Enable::enable` would also pulse
+    /// the shared reset and clear the parent block's I2S configuration.
+    #[doc(hidden)]
+    fn enable_ext() {}
+}
+
+// All STM32F4 models use the same bits in APB1ENR, APB2ENR, APB1RSTR, and APB2RSTR to enable
+// and reset the SPI peripherals.
+// SPI1: APB2 bit 12
+// SPI2: APB1 bit 14
+// SPI3: APB1 bit 15
+// SPI4: APB2 bit 13
+// SPI5: APB2 bit 20
+
+#[cfg(any(
+    feature = "stm32f410",
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f423",
+    feature = "stm32f446",
+))]
+mod spi1 {
+    use super::sealed::Sealed;
+    use super::{Enable, Instance, NoMasterClock, PinMck};
+    use crate::bb;
+    use crate::pac::{RCC, SPI1};
+    impl Sealed for SPI1 {}
+    impl Enable for SPI1 {
+        fn enable() {
+            unsafe {
+                // NOTE(unsafe) this reference will only be used for atomic writes with no side effects.
+                let rcc = &(*RCC::ptr());
+                const SPI_BIT: u8 = 12;
+                // Enable clock, enable reset, clear, reset
+                bb::set(&rcc.apb2enr, SPI_BIT);
+                bb::set(&rcc.apb2rstr, SPI_BIT);
+                bb::clear(&rcc.apb2rstr, SPI_BIT);
+            }
+        }
+    }
+    impl Instance for SPI1 {
+        fn ptr() -> *const crate::pac::spi1::RegisterBlock {
+            SPI1::ptr()
+        }
+    }
+    impl PinMck<SPI1> for NoMasterClock {
+        fn is_master_clock(&self) -> bool {
+            false
+        }
+    }
+}
+
+// All STM32F4 models support SPI2/I2S2
+mod spi2 {
+    use super::sealed::Sealed;
+    use super::{Enable, Instance, NoMasterClock, PinMck};
+    use crate::bb;
+    use crate::pac::{RCC, SPI2};
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    use super::HasExt;
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    use crate::pac::I2S2EXT;
+    impl Sealed for SPI2 {}
+    impl Enable for SPI2 {
+        fn enable() {
+            unsafe {
+                // NOTE(unsafe) this reference will only be used for atomic writes with no side effects.
+                let rcc = &(*RCC::ptr());
+                const SPI_BIT: u8 = 14;
+                // Enable clock, enable reset, clear, reset
+                bb::set(&rcc.apb1enr, SPI_BIT);
+                bb::set(&rcc.apb1rstr, SPI_BIT);
+                bb::clear(&rcc.apb1rstr, SPI_BIT);
+            }
+        }
+    }
+    impl Instance for SPI2 {
+        fn ptr() -> *const crate::pac::spi1::RegisterBlock {
+            SPI2::ptr()
+        }
+    }
+    #[cfg(any(
+        feature = "stm32f401",
+        feature = "stm32f405",
+        feature = "stm32f407",
+        feature = "stm32f411",
+        feature = "stm32f412",
+        feature = "stm32f413",
+        feature = "stm32f415",
+        feature = "stm32f417",
+        feature = "stm32f423",
+        feature = "stm32f427",
+        feature = "stm32f429",
+        feature = "stm32f437",
+        feature = "stm32f439",
+        feature = "stm32f446",
+        feature = "stm32f469",
+        feature = "stm32f479",
+    ))]
+    impl HasExt for SPI2 {
+        fn ext_ptr() -> *const crate::pac::spi1::RegisterBlock {
+            I2S2EXT::ptr()
+        }
+    }
+    impl PinMck<SPI2> for NoMasterClock {
+        fn is_master_clock(&self) -> bool {
+            false
+        }
+    }
+}
+
+// All STM32F4 models except STM32F410 support SPI3/I2S3
+#[cfg(any(
+    feature = "stm32f401",
+    feature = "stm32f405",
+    feature = "stm32f407",
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f415",
+    feature = "stm32f417",
+    feature = "stm32f423",
+    feature = "stm32f427",
+    feature = "stm32f429",
+    feature = "stm32f437",
+    feature = "stm32f439",
+    feature = "stm32f446",
+    feature = "stm32f469",
+    feature = "stm32f479",
+))]
+mod spi3 {
+    use super::sealed::Sealed;
+    use super::{Enable, HasExt, Instance, NoMasterClock, PinMck};
+    use crate::bb;
+    use crate::pac::{I2S3EXT, RCC, SPI3};
+    impl Sealed for SPI3 {}
+    impl Enable for SPI3 {
+        fn enable() {
+            unsafe {
+                // NOTE(unsafe) this reference will only be used for atomic writes with no side effects.
+                let rcc = &(*RCC::ptr());
+                const SPI_BIT: u8 = 15;
+                // Enable clock, enable reset, clear, reset
+                bb::set(&rcc.apb1enr, SPI_BIT);
+                bb::set(&rcc.apb1rstr, SPI_BIT);
+                bb::clear(&rcc.apb1rstr, SPI_BIT);
+            }
+        }
+    }
+    impl Instance for SPI3 {
+        fn ptr() -> *const crate::pac::spi1::RegisterBlock {
+            SPI3::ptr()
+        }
+    }
+    impl HasExt for SPI3 {
+        fn ext_ptr() -> *const crate::pac::spi1::RegisterBlock {
+            I2S3EXT::ptr()
+        }
+    }
+    impl PinMck<SPI3> for NoMasterClock {
+        fn is_master_clock(&self) -> bool {
+            false
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f423",
+))]
+mod spi4 {
+    use super::sealed::Sealed;
+    use super::{Enable, Instance, NoMasterClock, PinMck};
+    use crate::bb;
+    use crate::pac::{RCC, SPI4};
+    impl Sealed for SPI4 {}
+    impl Enable for SPI4 {
+        fn enable() {
+            unsafe {
+                // NOTE(unsafe) this reference will only be used for atomic writes with no side effects.
+                let rcc = &(*RCC::ptr());
+                const SPI_BIT: u8 = 13;
+                // Enable clock, enable reset, clear, reset
+                bb::set(&rcc.apb2enr, SPI_BIT);
+                bb::set(&rcc.apb2rstr, SPI_BIT);
+                bb::clear(&rcc.apb2rstr, SPI_BIT);
+            }
+        }
+    }
+    impl Instance for SPI4 {
+        fn ptr() -> *const crate::pac::spi1::RegisterBlock {
+            SPI4::ptr()
+        }
+    }
+    impl PinMck<SPI4> for NoMasterClock {
+        fn is_master_clock(&self) -> bool {
+            false
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "stm32f410",
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f423",
+))]
+mod spi5 {
+    use super::sealed::Sealed;
+    use super::{Enable, Instance, NoMasterClock, PinMck};
+    use crate::bb;
+    use crate::pac::{RCC, SPI5};
+    impl Sealed for SPI5 {}
+    impl Enable for SPI5 {
+        fn enable() {
+            unsafe {
+                // NOTE(unsafe) this reference will only be used for atomic writes with no side effects.
+                let rcc = &(*RCC::ptr());
+                const SPI_BIT: u8 = 20;
+                // Enable clock, enable reset, clear, reset
+                bb::set(&rcc.apb2enr, SPI_BIT);
+                bb::set(&rcc.apb2rstr, SPI_BIT);
+                bb::clear(&rcc.apb2rstr, SPI_BIT);
+            }
+        }
+    }
+    impl Instance for SPI5 {
+        fn ptr() -> *const crate::pac::spi1::RegisterBlock {
+            SPI5::ptr()
+        }
+    }
+    impl PinMck<SPI5> for NoMasterClock {
+        fn is_master_clock(&self) -> bool {
+            false
+        }
+    }
+}
+
+/// The frame format used on the I2S bus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum I2sStandard {
+    /// I2S Philips standard
+    Philips,
+    /// MSB-justified standard
+    MsbJustified,
+    /// LSB-justified standard
+    LsbJustified,
+    /// PCM standard with a short (one-cycle) frame synchronization pulse
+    PcmShortFrame,
+    /// PCM standard with a long (one-channel-length) frame synchronization pulse
+    PcmLongFrame,
+}
+
+impl I2sStandard {
+    fn i2sstd_bits(self) -> u8 {
+        match self {
+            I2sStandard::Philips => 0b00,
+            I2sStandard::MsbJustified => 0b01,
+            I2sStandard::LsbJustified => 0b10,
+            I2sStandard::PcmShortFrame | I2sStandard::PcmLongFrame => 0b11,
+        }
+    }
+
+    fn pcmsync_long_frame(self) -> bool {
+        matches!(self, I2sStandard::PcmLongFrame)
+    }
+}
+
+/// The number of bits used to represent one audio sample on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataLength {
+    /// 16-bit samples
+    Bits16,
+    /// 24-bit samples (requires a 32-bit channel)
+    Bits24,
+    /// 32-bit samples (requires a 32-bit channel)
+    Bits32,
+}
+
+impl DataLength {
+    fn datlen_bits(self) -> u8 {
+        match self {
+            DataLength::Bits16 => 0b00,
+            DataLength::Bits24 => 0b01,
+            DataLength::Bits32 => 0b10,
+        }
+    }
+}
+
+/// The number of SCK cycles used for one audio channel (half of one WS period)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelLength {
+    /// 16 SCK cycles per channel
+    Bits16,
+    /// 32 SCK cycles per channel
+    Bits32,
+}
+
+/// The idle state of the bit clock (CK) line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockPolarity {
+    /// CK idles low
+    IdleLow,
+    /// CK idles high
+    IdleHigh,
+}
+
+/// The role an I2S interface plays on the bus, and the direction samples flow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// This interface drives WS and CK and transmits samples on SD
+    MasterTransmit,
+    /// This interface drives WS and CK and receives samples on SD
+    MasterReceive,
+    /// This interface follows WS and CK driven by another device and transmits samples on SD
+    SlaveTransmit,
+    /// This interface follows WS and CK driven by another device and receives samples on SD
+    SlaveReceive,
+}
+
+impl Mode {
+    fn i2scfg_bits(self) -> u8 {
+        match self {
+            Mode::SlaveTransmit => 0b00,
+            Mode::SlaveReceive => 0b01,
+            Mode::MasterTransmit => 0b10,
+            Mode::MasterReceive => 0b11,
+        }
+    }
+}
+
+/// Configuration for an `I2s` peripheral
+///
+/// The default, from `Config::new`, is the Philips standard with 16-bit samples in a 16-bit
+/// wide channel and a bit clock that idles low. Master clock output is enabled automatically
+/// whenever the pins passed to `I2s::new` include a real `PinMck` pin.
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    mode: Mode,
+    standard: I2sStandard,
+    data_length: DataLength,
+    channel_length: ChannelLength,
+    polarity: ClockPolarity,
+}
+
+impl Config {
+    /// Creates a configuration for the given mode, with the defaults described on `Config`
+    pub fn new(mode: Mode) -> Self {
+        Config {
+            mode,
+            standard: I2sStandard::Philips,
+            data_length: DataLength::Bits16,
+            channel_length: ChannelLength::Bits16,
+            polarity: ClockPolarity::IdleLow,
+        }
+    }
+
+    /// Sets the audio standard (frame format)
+    pub fn standard(mut self, standard: I2sStandard) -> Self {
+        self.standard = standard;
+        self
+    }
+
+    /// Sets the sample size
+    pub fn data_length(mut self, data_length: DataLength) -> Self {
+        self.data_length = data_length;
+        self
+    }
+
+    /// Sets the number of SCK cycles per audio channel
+    pub fn channel_length(mut self, channel_length: ChannelLength) -> Self {
+        self.channel_length = channel_length;
+        self
+    }
+
+    /// Sets the idle state of the bit clock line
+    pub fn polarity(mut self, polarity: ClockPolarity) -> Self {
+        self.polarity = polarity;
+        self
+    }
+}
+
+/// An error that can occur when configuring or using an `I2s` peripheral
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The combination of `DataLength` and `ChannelLength` in the `Config` is not supported by
+    /// the hardware (24-bit and 32-bit samples require a 32-bit channel)
+    InvalidFrameFormat,
+    /// No `I2SDIV`/`ODD` pair in the valid range (`I2SDIV` from 2 to 255) produces the requested
+    /// sample rate from the supplied I2S input clock
+    SampleRateUnreachable,
+    /// The receive data register overrun flag (OVR) was set while reading a sample
+    Overrun,
+}
+
+/// An I2S (inter-IC Sound) interface built on top of an SPI peripheral
+///
+/// `SPI` is the peripheral used, and `PINS` is a tuple `(WS, CK, MCLK, SD)` of pins connected to
+/// it, as accepted by the `Pins` trait.
+pub struct I2s<SPI, PINS> {
+    spi: SPI,
+    pins: PINS,
+    config: Config,
+}
+
+impl<SPI, PINS> I2s<SPI, PINS>
+where
+    SPI: Instance,
+    PINS: Pins<SPI>,
+{
+    /// Configures `spi` and `pins` for I2S communication at `sample_rate`.
+    ///
+    /// `i2s_clk` is the frequency of the I2Sx clock driving the peripheral's prescaler (usually
+    /// derived from PLLI2S), used together with `sample_rate` to compute `I2SDIV`/`ODD`.
+    /// `clocks` is required as evidence that the clock tree has already been configured.
+    pub fn new(
+        spi: SPI,
+        pins: PINS,
+        i2s_clk: Hertz,
+        sample_rate: Hertz,
+        config: Config,
+        clocks: &Clocks,
+    ) -> Result<Self, Error> {
+        let _ = clocks;
+
+        match (config.data_length, config.channel_length) {
+            (DataLength::Bits16, _)
+            | (DataLength::Bits24, ChannelLength::Bits32)
+            | (DataLength::Bits32, ChannelLength::Bits32) => {}
+            _ => return Err(Error::InvalidFrameFormat),
+        }
+
+        let master_clock = pins.master_clock();
+        let is_slave = matches!(config.mode, Mode::SlaveTransmit | Mode::SlaveReceive);
+        let (i2sdiv, odd) = if is_slave {
+            // Slave modes receive their bit and word clocks externally; the peripheral doesn't
+            // use I2SDIV/ODD/MCKOE to generate them, so there's no divisor to compute from
+            // `i2s_clk`/`sample_rate`, and one that happens not to fit I2SDIV's 2..=255 range
+            // shouldn't reject an otherwise-valid slave configuration.
+            (2u8, false)
+        } else {
+            let denom: u32 = if master_clock {
+                256
+            } else {
+                match config.channel_length {
+                    ChannelLength::Bits16 => 32,
+                    ChannelLength::Bits32 => 64,
+                }
+            };
+            let divisor = i2s_clk.0 / (denom * sample_rate.0);
+            let i2sdiv = divisor / 2;
+            let odd = divisor % 2 != 0;
+            if !(2..=255).contains(&i2sdiv) {
+                return Err(Error::SampleRateUnreachable);
+            }
+            (i2sdiv as u8, odd)
+        };
+
+        SPI::enable();
+
+        let spi_rb = unsafe { &*SPI::ptr() };
+        spi_rb.i2spr.write(|w| unsafe {
+            w.i2sdiv()
+                .bits(i2sdiv)
+                .odd()
+                .bit(odd)
+                .mckoe()
+                .bit(master_clock && !is_slave)
+        });
+        spi_rb.i2scfgr.write(|w| unsafe {
+            w.i2smod()
+                .bit(true)
+                .i2scfg()
+                .bits(config.mode.i2scfg_bits())
+                .pcmsync()
+                .bit(config.standard.pcmsync_long_frame())
+                .i2sstd()
+                .bits(config.standard.i2sstd_bits())
+                .ckpol()
+                .bit(config.polarity == ClockPolarity::IdleHigh)
+                .datlen()
+                .bits(config.data_length.datlen_bits())
+                .chlen()
+                .bit(config.channel_length == ChannelLength::Bits32)
+        });
+        spi_rb.i2scfgr.modify(|_, w| w.i2se().bit(true));
+
+        Ok(I2s { spi, pins, config })
+    }
+
+    /// Returns the configuration this interface was built with
+    pub fn config(&self) -> Config {
+        self.config
+    }
+
+    /// Waits for any in-progress transfer to finish, disables the peripheral, and returns the
+    /// underlying SPI peripheral and pins
+    pub fn release(self) -> (SPI, PINS) {
+        let spi_rb = unsafe { &*SPI::ptr() };
+        while spi_rb.sr.read().bsy().bit_is_set() {}
+        spi_rb.i2scfgr.modify(|_, w| w.i2se().bit(false));
+        (self.spi, self.pins)
+    }
+
+    /// Blocks until the transmit data register is empty, then writes one sample
+    pub fn write_sample(&mut self, sample: u16) -> Result<(), Error> {
+        let spi_rb = unsafe { &*SPI::ptr() };
+        while spi_rb.sr.read().txe().bit_is_clear() {}
+        spi_rb.dr.write(|w| unsafe { w.dr().bits(sample) });
+        Ok(())
+    }
+
+    /// Blocks until a sample is available, then reads it, returning `Error::Overrun` if the
+    /// receive data register overrun flag was set
+    pub fn read_sample(&mut self) -> Result<u16, Error> {
+        let spi_rb = unsafe { &*SPI::ptr() };
+        while spi_rb.sr.read().rxne().bit_is_clear() {}
+        // The OVR flag is cleared by reading DR followed by reading SR, so this sequence must
+        // run even on the error path or every subsequent read would also report an overrun.
+        let sample = spi_rb.dr.read().dr().bits();
+        let sr = spi_rb.sr.read();
+        if sr.ovr().bit_is_set() {
+            return Err(Error::Overrun);
+        }
+        Ok(sample)
+    }
+
+    /// Hands this interface's data register to the DMA subsystem, returning a builder used to
+    /// split off a `Tx` or `Rx` endpoint for continuous, non-polling audio streaming.
+    ///
+    /// Use `Tx::circular_write` for master/slave-transmit configurations (playback) or
+    /// `Rx::circular_read` for master/slave-receive configurations (capture), handing either one
+    /// a caller-supplied double buffer so the application can refill or drain whichever half is
+    /// idle from the `Transfer`'s half-transfer and transfer-complete interrupts.
+    pub fn use_dma(self) -> I2sDma<SPI, PINS> {
+        I2sDma { i2s: self }
+    }
+}
+
+impl<SPI, PWS, PCK, PMCLK, PSD> I2s<SPI, (PWS, PCK, PMCLK, PSD)>
+where
+    SPI: Instance,
+    PWS: PinWs<SPI>,
+    PCK: PinCk<SPI>,
+    PMCLK: PinMck<SPI>,
+    PSD: PinSd<SPI>,
+{
+    /// Configures `spi` for I2S communication at `sample_rate`, taking `ws` and `mck` as `impl
+    /// Into<_>` so the enum-based pin selections (e.g. `WsPin2`, `MckPin2`) can be built from a
+    /// concrete pin at the call site instead of having to be wrapped by hand first.
+    ///
+    /// Otherwise identical to `new`, which this delegates to once the pins are assembled into the
+    /// `(WS, CK, MCLK, SD)` tuple `Pins` is implemented for.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_with_pins(
+        spi: SPI,
+        ws: impl Into<PWS>,
+        ck: PCK,
+        mck: impl Into<PMCLK>,
+        sd: PSD,
+        i2s_clk: Hertz,
+        sample_rate: Hertz,
+        config: Config,
+        clocks: &Clocks,
+    ) -> Result<Self, Error> {
+        Self::new(
+            spi,
+            (ws.into(), ck, mck.into(), sd),
+            i2s_clk,
+            sample_rate,
+            config,
+            clocks,
+        )
+    }
+}
+
+/// A DMA-capable `I2s` interface, produced by `I2s::use_dma`
+pub struct I2sDma<SPI, PINS> {
+    i2s: I2s<SPI, PINS>,
+}
+
+impl<SPI, PINS> I2sDma<SPI, PINS>
+where
+    SPI: Instance,
+{
+    /// Reclaims the underlying `I2s` interface
+    pub fn release(self) -> I2s<SPI, PINS> {
+        self.i2s
+    }
+
+    /// Splits off the transmit half, for streaming samples out over DMA, and the pins, so the
+    /// peripheral reclaimed from `Tx::release` (once the `Transfer` is freed) can be paired back
+    /// up with them.
+    pub fn tx(self) -> (Tx<SPI>, PINS) {
+        (
+            Tx {
+                spi: self.i2s.spi,
+            },
+            self.i2s.pins,
+        )
+    }
+
+    /// Splits off the receive half, for streaming samples in over DMA, and the pins, so the
+    /// peripheral reclaimed from `Rx::release` (once the `Transfer` is freed) can be paired back
+    /// up with them.
+    pub fn rx(self) -> (Rx<SPI>, PINS) {
+        (
+            Rx {
+                spi: self.i2s.spi,
+            },
+            self.i2s.pins,
+        )
+    }
+}
+
+/// The transmit half of an `I2s` interface, handed to a DMA stream as a peripheral endpoint
+pub struct Tx<SPI> {
+    spi: SPI,
+}
+
+/// The receive half of an `I2s` interface, handed to a DMA stream as a peripheral endpoint
+pub struct Rx<SPI> {
+    spi: SPI,
+}
+
+impl<SPI> Tx<SPI> {
+    /// Reclaims the underlying SPI peripheral, e.g. after reading it back out of a finished
+    /// `Transfer` with `Transfer::free`
+    pub fn release(self) -> SPI {
+        self.spi
+    }
+}
+
+impl<SPI> Rx<SPI> {
+    /// Reclaims the underlying SPI peripheral, e.g. after reading it back out of a finished
+    /// `Transfer` with `Transfer::free`
+    pub fn release(self) -> SPI {
+        self.spi
+    }
+}
+
+unsafe impl<SPI: Instance> PeriAddress for Tx<SPI> {
+    #[inline(always)]
+    fn address(&self) -> u32 {
+        unsafe { &(*SPI::ptr()).dr as *const _ as u32 }
+    }
+
+    type MemSize = u16;
+}
+
+unsafe impl<SPI: Instance> PeriAddress for Rx<SPI> {
+    #[inline(always)]
+    fn address(&self) -> u32 {
+        unsafe { &(*SPI::ptr()).dr as *const _ as u32 }
+    }
+
+    type MemSize = u16;
+}
+
+impl<SPI> Tx<SPI>
+where
+    SPI: Instance,
+{
+    /// Starts a circular, double-buffered DMA transfer that continuously streams samples from
+    /// `buffer` into this interface's data register, for uninterrupted audio playback (master or
+    /// slave transmit).
+    ///
+    /// The DMA stream runs in double-buffer mode over the two halves of `buffer`: the returned
+    /// `Transfer` fires a transfer-complete interrupt each time one half has been sent (and a
+    /// half-transfer interrupt halfway through each half), so the application can refill
+    /// whichever half just finished while the DMA stream sends the other.
+    pub fn circular_write<STREAM, const CHANNEL: u8>(
+        self,
+        stream: STREAM,
+        buffer: [&'static mut [u16]; 2],
+    ) -> Transfer<STREAM, CHANNEL, Self, MemoryToPeripheral, &'static mut [u16]>
+    where
+        Self: DMASet<STREAM, CHANNEL, MemoryToPeripheral>,
+    {
+        // The DMA controller only reacts to this stream's requests once the peripheral itself
+        // asserts them; without setting TXDMAEN the I2S block never raises a DMA request and the
+        // transfer would stall forever.
+        let spi_rb = unsafe { &*SPI::ptr() };
+        spi_rb.cr2.modify(|_, w| w.txdmaen().bit(true));
+
+        let [first, second] = buffer;
+        Transfer::init(
+            stream,
+            self,
+            first,
+            Some(second),
+            DmaConfig::default()
+                .memory_increment(true)
+                .double_buffer(true)
+                .transfer_complete_interrupt(true)
+                .half_transfer_interrupt(true),
+        )
+    }
+}
+
+impl<SPI> Rx<SPI>
+where
+    SPI: Instance,
+{
+    /// Starts a circular, double-buffered DMA transfer that continuously streams samples from
+    /// this interface's data register into `buffer`, for uninterrupted audio capture (master or
+    /// slave receive).
+    ///
+    /// The DMA stream runs in double-buffer mode over the two halves of `buffer`: the returned
+    /// `Transfer` fires a transfer-complete interrupt each time one half has been filled (and a
+    /// half-transfer interrupt halfway through each half), so the application can drain
+    /// whichever half just finished while the DMA stream fills the other.
+    pub fn circular_read<STREAM, const CHANNEL: u8>(
+        self,
+        stream: STREAM,
+        buffer: [&'static mut [u16]; 2],
+    ) -> Transfer<STREAM, CHANNEL, Self, PeripheralToMemory, &'static mut [u16]>
+    where
+        Self: DMASet<STREAM, CHANNEL, PeripheralToMemory>,
+    {
+        // See the matching comment in `Tx::circular_write`: RXDMAEN must be set or the I2S block
+        // never asserts a DMA request.
+        let spi_rb = unsafe { &*SPI::ptr() };
+        spi_rb.cr2.modify(|_, w| w.rxdmaen().bit(true));
+
+        let [first, second] = buffer;
+        Transfer::init(
+            stream,
+            self,
+            first,
+            Some(second),
+            DmaConfig::default()
+                .memory_increment(true)
+                .double_buffer(true)
+                .transfer_complete_interrupt(true)
+                .half_transfer_interrupt(true),
+        )
+    }
+}
+
+// Valid DMA stream/channel pairs for each I2S-capable SPI instance's TX and RX requests, as
+// listed in the reference manual's DMA request mapping table.
+#[cfg(any(
+    feature = "stm32f410",
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f423",
+    feature = "stm32f446",
+))]
+dma::dma_map!(
+    (Tx<pac::SPI1>, dma::Stream3<pac::DMA2>, dma::Channel3), // SPI1_TX
+    (Tx<pac::SPI1>, dma::Stream5<pac::DMA2>, dma::Channel3), // SPI1_TX
+    (Rx<pac::SPI1>, dma::Stream0<pac::DMA2>, dma::Channel3), // SPI1_RX
+    (Rx<pac::SPI1>, dma::Stream2<pac::DMA2>, dma::Channel3), // SPI1_RX
+);
+
+dma::dma_map!(
+    (Tx<pac::SPI2>, dma::Stream4<pac::DMA1>, dma::Channel0), // SPI2_TX
+    (Rx<pac::SPI2>, dma::Stream3<pac::DMA1>, dma::Channel0), // SPI2_RX
+);
+
+#[cfg(any(
+    feature = "stm32f401",
+    feature = "stm32f405",
+    feature = "stm32f407",
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f415",
+    feature = "stm32f417",
+    feature = "stm32f423",
+    feature = "stm32f427",
+    feature = "stm32f429",
+    feature = "stm32f437",
+    feature = "stm32f439",
+    feature = "stm32f446",
+    feature = "stm32f469",
+    feature = "stm32f479",
+))]
+dma::dma_map!(
+    (Tx<pac::SPI3>, dma::Stream5<pac::DMA1>, dma::Channel0), // SPI3_TX
+    (Tx<pac::SPI3>, dma::Stream7<pac::DMA1>, dma::Channel0), // SPI3_TX
+    (Rx<pac::SPI3>, dma::Stream0<pac::DMA1>, dma::Channel0), // SPI3_RX
+    (Rx<pac::SPI3>, dma::Stream2<pac::DMA1>, dma::Channel0), // SPI3_RX
+);
+
+#[cfg(any(
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f423",
+))]
+dma::dma_map!(
+    (Tx<pac::SPI4>, dma::Stream1<pac::DMA2>, dma::Channel4), // SPI4_TX
+    (Tx<pac::SPI4>, dma::Stream4<pac::DMA2>, dma::Channel5), // SPI4_TX
+    (Rx<pac::SPI4>, dma::Stream0<pac::DMA2>, dma::Channel4), // SPI4_RX
+    (Rx<pac::SPI4>, dma::Stream3<pac::DMA2>, dma::Channel5), // SPI4_RX
+);
+
+#[cfg(any(
+    feature = "stm32f410",
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f423",
+))]
+dma::dma_map!(
+    (Tx<pac::SPI5>, dma::Stream4<pac::DMA2>, dma::Channel2), // SPI5_TX
+    (Tx<pac::SPI5>, dma::Stream6<pac::DMA2>, dma::Channel7), // SPI5_TX
+    (Rx<pac::SPI5>, dma::Stream3<pac::DMA2>, dma::Channel2), // SPI5_RX
+    (Rx<pac::SPI5>, dma::Stream5<pac::DMA2>, dma::Channel7), // SPI5_RX
+);
+
+/// A full-duplex I2S interface that drives both the main `I2Sx` block and its `I2Sxext`
+/// extension block, transmitting and receiving at the same time over the shared WS/CK pins.
+///
+/// `I2Sxext` always runs as a slave in the direction opposite `i2s`, so pairing a
+/// master-transmit or slave-transmit `i2s` gives a receiving ext block (and vice versa) -
+/// the building block for codec loopback and echo-cancellation front ends.
+pub struct DualI2s<SPI, PINS, ESD> {
+    i2s: I2s<SPI, PINS>,
+    ext_sd: ESD,
+}
+
+impl<SPI, PINS, ESD> DualI2s<SPI, PINS, ESD>
+where
+    SPI: HasExt,
+    PINS: Pins<SPI>,
+    ESD: PinSdExt<SPI>,
+{
+    /// Enables and configures the `I2Sxext` block to run opposite `i2s`'s direction, using
+    /// `i2s`'s audio standard, sample format and clock polarity, and returns the combined
+    /// full-duplex interface.
+    pub fn new(i2s: I2s<SPI, PINS>, ext_sd: ESD) -> Self {
+        let ext_mode = match i2s.config.mode {
+            Mode::MasterTransmit | Mode::SlaveTransmit => Mode::SlaveReceive,
+            Mode::MasterReceive | Mode::SlaveReceive => Mode::SlaveTransmit,
+        };
+
+        SPI::enable_ext();
+
+        let ext_rb = unsafe { &*SPI::ext_ptr() };
+        ext_rb.i2scfgr.write(|w| unsafe {
+            w.i2smod()
+                .bit(true)
+                .i2scfg()
+                .bits(ext_mode.i2scfg_bits())
+                .pcmsync()
+                .bit(i2s.config.standard.pcmsync_long_frame())
+                .i2sstd()
+                .bits(i2s.config.standard.i2sstd_bits())
+                .ckpol()
+                .bit(i2s.config.polarity == ClockPolarity::IdleHigh)
+                .datlen()
+                .bits(i2s.config.data_length.datlen_bits())
+                .chlen()
+                .bit(i2s.config.channel_length == ChannelLength::Bits32)
+        });
+        ext_rb.i2scfgr.modify(|_, w| w.i2se().bit(true));
+
+        DualI2s { i2s, ext_sd }
+    }
+
+    /// Disables the `I2Sxext` block and returns the main `I2s` interface and the ext SD pin
+    pub fn release(self) -> (I2s<SPI, PINS>, ESD) {
+        let ext_rb = unsafe { &*SPI::ext_ptr() };
+        while ext_rb.sr.read().bsy().bit_is_set() {}
+        ext_rb.i2scfgr.modify(|_, w| w.i2se().bit(false));
+        (self.i2s, self.ext_sd)
+    }
+
+    /// Blocks until the main block's transmit data register is empty, then writes one sample
+    pub fn write_sample(&mut self, sample: u16) -> Result<(), Error> {
+        self.i2s.write_sample(sample)
+    }
+
+    /// Blocks until the main block has a sample available, then reads it
+    pub fn read_sample(&mut self) -> Result<u16, Error> {
+        self.i2s.read_sample()
+    }
+
+    /// Blocks until the ext block's transmit data register is empty, then writes one sample
+    pub fn write_sample_ext(&mut self, sample: u16) -> Result<(), Error> {
+        let ext_rb = unsafe { &*SPI::ext_ptr() };
+        while ext_rb.sr.read().txe().bit_is_clear() {}
+        ext_rb.dr.write(|w| unsafe { w.dr().bits(sample) });
+        Ok(())
+    }
+
+    /// Blocks until the ext block has a sample available, then reads it
+    pub fn read_sample_ext(&mut self) -> Result<u16, Error> {
+        let ext_rb = unsafe { &*SPI::ext_ptr() };
+        while ext_rb.sr.read().rxne().bit_is_clear() {}
+        // As in `I2s::read_sample`, OVR is cleared by reading DR followed by reading SR, so this
+        // sequence must run even on the error path or every subsequent read would also report an
+        // overrun.
+        let sample = ext_rb.dr.read().dr().bits();
+        let sr = ext_rb.sr.read();
+        if sr.ovr().bit_is_set() {
+            return Err(Error::Overrun);
+        }
+        Ok(sample)
+    }
+
+    /// Hands the ext block's data register to the DMA subsystem, returning a builder used to
+    /// split off a `Tx` or `Rx` endpoint for the ext block, mirroring `I2s::use_dma`.
+    pub fn use_dma_ext(self) -> ExtDma<SPI, PINS, ESD> {
+        ExtDma { dual: self }
+    }
+}
+
+/// A DMA-capable `I2Sxext` extension block, produced by `DualI2s::use_dma_ext`
+pub struct ExtDma<SPI, PINS, ESD> {
+    dual: DualI2s<SPI, PINS, ESD>,
+}
+
+impl<SPI, PINS, ESD> ExtDma<SPI, PINS, ESD>
+where
+    SPI: HasExt,
+{
+    /// Reclaims the underlying `DualI2s` interface
+    pub fn release(self) -> DualI2s<SPI, PINS, ESD> {
+        self.dual
+    }
+
+    /// Splits off the ext block's transmit half, for streaming samples out over DMA, and the
+    /// pins and ext SD pin, so the peripheral reclaimed from `ExtTx::release` (once the
+    /// `Transfer` is freed) can be paired back up with them.
+    pub fn tx(self) -> (ExtTx<SPI>, PINS, ESD) {
+        (
+            ExtTx {
+                spi: self.dual.i2s.spi,
+            },
+            self.dual.i2s.pins,
+            self.dual.ext_sd,
+        )
+    }
+
+    /// Splits off the ext block's receive half, for streaming samples in over DMA, and the
+    /// pins and ext SD pin, so the peripheral reclaimed from `ExtRx::release` (once the
+    /// `Transfer` is freed) can be paired back up with them.
+    pub fn rx(self) -> (ExtRx<SPI>, PINS, ESD) {
+        (
+            ExtRx {
+                spi: self.dual.i2s.spi,
+            },
+            self.dual.i2s.pins,
+            self.dual.ext_sd,
+        )
+    }
+}
+
+/// The transmit half of an `I2Sxext` extension block, handed to a DMA stream as a peripheral
+/// endpoint
+pub struct ExtTx<SPI> {
+    spi: SPI,
+}
+
+/// The receive half of an `I2Sxext` extension block, handed to a DMA stream as a peripheral
+/// endpoint
+pub struct ExtRx<SPI> {
+    spi: SPI,
+}
+
+impl<SPI> ExtTx<SPI> {
+    /// Reclaims the underlying SPI peripheral, e.g. after reading it back out of a finished
+    /// `Transfer` with `Transfer::free`
+    pub fn release(self) -> SPI {
+        self.spi
+    }
+}
+
+impl<SPI> ExtRx<SPI> {
+    /// Reclaims the underlying SPI peripheral, e.g. after reading it back out of a finished
+    /// `Transfer` with `Transfer::free`
+    pub fn release(self) -> SPI {
+        self.spi
+    }
+}
+
+unsafe impl<SPI: HasExt> PeriAddress for ExtTx<SPI> {
+    #[inline(always)]
+    fn address(&self) -> u32 {
+        unsafe { &(*SPI::ext_ptr()).dr as *const _ as u32 }
+    }
+
+    type MemSize = u16;
+}
+
+unsafe impl<SPI: HasExt> PeriAddress for ExtRx<SPI> {
+    #[inline(always)]
+    fn address(&self) -> u32 {
+        unsafe { &(*SPI::ext_ptr()).dr as *const _ as u32 }
+    }
+
+    type MemSize = u16;
+}
+
+impl<SPI> ExtTx<SPI>
+where
+    SPI: HasExt,
+{
+    /// Starts a circular, double-buffered DMA transfer that continuously streams samples from
+    /// `buffer` into the ext block's data register
+    pub fn circular_write<STREAM, const CHANNEL: u8>(
+        self,
+        stream: STREAM,
+        buffer: [&'static mut [u16]; 2],
+    ) -> Transfer<STREAM, CHANNEL, Self, MemoryToPeripheral, &'static mut [u16]>
+    where
+        Self: DMASet<STREAM, CHANNEL, MemoryToPeripheral>,
+    {
+        // As in `Tx::circular_write`, the ext block needs its own TXDMAEN set or it never
+        // asserts a DMA request.
+        let ext_rb = unsafe { &*SPI::ext_ptr() };
+        ext_rb.cr2.modify(|_, w| w.txdmaen().bit(true));
+
+        let [first, second] = buffer;
+        Transfer::init(
+            stream,
+            self,
+            first,
+            Some(second),
+            DmaConfig::default()
+                .memory_increment(true)
+                .double_buffer(true)
+                .transfer_complete_interrupt(true)
+                .half_transfer_interrupt(true),
+        )
+    }
+}
+
+impl<SPI> ExtRx<SPI>
+where
+    SPI: HasExt,
+{
+    /// Starts a circular, double-buffered DMA transfer that continuously streams samples from
+    /// the ext block's data register into `buffer`
+    pub fn circular_read<STREAM, const CHANNEL: u8>(
+        self,
+        stream: STREAM,
+        buffer: [&'static mut [u16]; 2],
+    ) -> Transfer<STREAM, CHANNEL, Self, PeripheralToMemory, &'static mut [u16]>
+    where
+        Self: DMASet<STREAM, CHANNEL, PeripheralToMemory>,
+    {
+        // As in `Rx::circular_read`, the ext block needs its own RXDMAEN set or it never
+        // asserts a DMA request.
+        let ext_rb = unsafe { &*SPI::ext_ptr() };
+        ext_rb.cr2.modify(|_, w| w.rxdmaen().bit(true));
+
+        let [first, second] = buffer;
+        Transfer::init(
+            stream,
+            self,
+            first,
+            Some(second),
+            DmaConfig::default()
+                .memory_increment(true)
+                .double_buffer(true)
+                .transfer_complete_interrupt(true)
+                .half_transfer_interrupt(true),
+        )
+    }
+}
+
+// I2S2ext and I2S3ext request their own DMA channel, distinct from the main SPI2/SPI3 requests,
+// on the same streams. STM32F410 has no I2S2ext block, so it gets no map entries here either.
+#[cfg(any(
+    feature = "stm32f401",
+    feature = "stm32f405",
+    feature = "stm32f407",
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f415",
+    feature = "stm32f417",
+    feature = "stm32f423",
+    feature = "stm32f427",
+    feature = "stm32f429",
+    feature = "stm32f437",
+    feature = "stm32f439",
+    feature = "stm32f446",
+    feature = "stm32f469",
+    feature = "stm32f479",
+))]
+dma::dma_map!(
+    (ExtTx<pac::SPI2>, dma::Stream4<pac::DMA1>, dma::Channel2), // I2S2_EXT_TX
+    (ExtRx<pac::SPI2>, dma::Stream3<pac::DMA1>, dma::Channel3), // I2S2_EXT_RX
+);
+
+#[cfg(any(
+    feature = "stm32f401",
+    feature = "stm32f405",
+    feature = "stm32f407",
+    feature = "stm32f411",
+    feature = "stm32f412",
+    feature = "stm32f413",
+    feature = "stm32f415",
+    feature = "stm32f417",
+    feature = "stm32f423",
+    feature = "stm32f427",
+    feature = "stm32f429",
+    feature = "stm32f437",
+    feature = "stm32f439",
+    feature = "stm32f446",
+    feature = "stm32f469",
+    feature = "stm32f479",
+))]
+dma::dma_map!(
+    (ExtTx<pac::SPI3>, dma::Stream5<pac::DMA1>, dma::Channel2), // I2S3_EXT_TX
+    (ExtRx<pac::SPI3>, dma::Stream0<pac::DMA1>, dma::Channel3), // I2S3_EXT_RX
+    (ExtRx<pac::SPI3>, dma::Stream2<pac::DMA1>, dma::Channel2), // I2S3_EXT_RX
+);